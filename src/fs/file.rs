@@ -26,6 +26,7 @@
  * SOFTWARE.
  */
 // -- ext
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::time::SystemTime;
 
@@ -39,6 +40,25 @@ pub enum Entry {
     File(File),
 }
 
+/// ## SymlinkError
+///
+/// Describes an error which may occur while resolving a symlink chain
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SymlinkError {
+    /// The symlink chain exceeded the maximum number of hops allowed, suggesting a loop
+    TooManyHops,
+}
+
+impl std::fmt::Display for SymlinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SymlinkError::TooManyHops => write!(f, "symlink chain exceeded the maximum number of hops"),
+        }
+    }
+}
+
+impl std::error::Error for SymlinkError {}
+
 /// ## Directory
 ///
 /// Directory provides an interface to file system directories
@@ -54,6 +74,8 @@ pub struct Directory {
     pub user: Option<u32>,                             // UNIX only
     pub group: Option<u32>,                            // UNIX only
     pub unix_pex: Option<(UnixPex, UnixPex, UnixPex)>, // UNIX only
+    pub unix_pex_special: Option<(bool, bool, bool)>,  // setuid, setgid, sticky; UNIX only
+    pub xattrs: BTreeMap<String, Vec<u8>>,             // extended attributes
 }
 
 /// ### File
@@ -73,6 +95,8 @@ pub struct File {
     pub user: Option<u32>,                             // UNIX only
     pub group: Option<u32>,                            // UNIX only
     pub unix_pex: Option<(UnixPex, UnixPex, UnixPex)>, // UNIX only
+    pub unix_pex_special: Option<(bool, bool, bool)>,  // setuid, setgid, sticky; UNIX only
+    pub xattrs: BTreeMap<String, Vec<u8>>,             // extended attributes
 }
 
 /// ## UnixPex
@@ -136,6 +160,131 @@ impl From<u8> for UnixPex {
     }
 }
 
+/// ## UnixMode
+///
+/// Describes the full POSIX mode of an entry: the three permission classes
+/// (owner, group, other) plus the special bits (setuid, setgid, sticky).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct UnixMode {
+    owner: UnixPex,
+    group: UnixPex,
+    other: UnixPex,
+    special: (bool, bool, bool), // (setuid, setgid, sticky)
+}
+
+impl UnixMode {
+    /// ### new
+    ///
+    /// Instantiates a new `UnixMode`
+    pub fn new(owner: UnixPex, group: UnixPex, other: UnixPex, special: (bool, bool, bool)) -> Self {
+        Self {
+            owner,
+            group,
+            other,
+            special,
+        }
+    }
+
+    /// ### owner
+    ///
+    /// Get the owner class permissions
+    pub fn owner(&self) -> UnixPex {
+        self.owner
+    }
+
+    /// ### group
+    ///
+    /// Get the group class permissions
+    pub fn group(&self) -> UnixPex {
+        self.group
+    }
+
+    /// ### other
+    ///
+    /// Get the other class permissions
+    pub fn other(&self) -> UnixPex {
+        self.other
+    }
+
+    /// ### setuid
+    ///
+    /// Returns whether the setuid bit is set
+    pub fn setuid(&self) -> bool {
+        self.special.0
+    }
+
+    /// ### setgid
+    ///
+    /// Returns whether the setgid bit is set
+    pub fn setgid(&self) -> bool {
+        self.special.1
+    }
+
+    /// ### sticky
+    ///
+    /// Returns whether the sticky bit is set
+    pub fn sticky(&self) -> bool {
+        self.special.2
+    }
+
+    /// ### as_mode
+    ///
+    /// Convert the `UnixMode` to its full octal representation, special bits included
+    pub fn as_mode(&self) -> u16 {
+        ((self.special.0 as u16) << 11)
+            | ((self.special.1 as u16) << 10)
+            | ((self.special.2 as u16) << 9)
+            | ((self.owner.as_byte() as u16) << 6)
+            | ((self.group.as_byte() as u16) << 3)
+            | (self.other.as_byte() as u16)
+    }
+
+    /// ### class_to_string
+    ///
+    /// Render a single permission class as its three-char `ls -l` representation,
+    /// substituting the execute slot with `set_bit`/`unset_bit` when `special` is set
+    fn class_to_string(pex: UnixPex, special: bool, set_bit: char, unset_bit: char) -> String {
+        format!(
+            "{}{}{}",
+            if pex.can_read() { 'r' } else { '-' },
+            if pex.can_write() { 'w' } else { '-' },
+            match (pex.can_execute(), special) {
+                (true, true) => set_bit,
+                (false, true) => unset_bit,
+                (true, false) => 'x',
+                (false, false) => '-',
+            }
+        )
+    }
+}
+
+impl From<u16> for UnixMode {
+    fn from(mode: u16) -> Self {
+        Self {
+            owner: UnixPex::from(((mode >> 6) & 0x07) as u8),
+            group: UnixPex::from(((mode >> 3) & 0x07) as u8),
+            other: UnixPex::from((mode & 0x07) as u8),
+            special: (
+                (mode & 0o4000) != 0,
+                (mode & 0o2000) != 0,
+                (mode & 0o1000) != 0,
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for UnixMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "-{}{}{}",
+            Self::class_to_string(self.owner, self.special.0, 's', 'S'),
+            Self::class_to_string(self.group, self.special.1, 's', 'S'),
+            Self::class_to_string(self.other, self.special.2, 't', 'T'),
+        )
+    }
+}
+
 impl Entry {
     /// ### get_abs_path
     ///
@@ -237,6 +386,90 @@ impl Entry {
         }
     }
 
+    /// ### get_mode
+    ///
+    /// Get the full unix mode from `Entry`, special bits included
+    pub fn get_mode(&self) -> Option<UnixMode> {
+        let (unix_pex, unix_pex_special) = match self {
+            Entry::Directory(dir) => (dir.unix_pex, dir.unix_pex_special),
+            Entry::File(file) => (file.unix_pex, file.unix_pex_special),
+        };
+        unix_pex.map(|(owner, group, other)| {
+            UnixMode::new(owner, group, other, unix_pex_special.unwrap_or_default())
+        })
+    }
+
+    /// ### can_read
+    ///
+    /// Returns whether `uid` (belonging to `gids`) can read this `Entry`, resolving the
+    /// effective POSIX permission class (owner, group or other) the way the kernel does.
+    /// Returns `None` if the `Entry` carries no unix permissions (e.g. non-UNIX backends).
+    pub fn can_read(&self, uid: u32, gids: &[u32]) -> Option<bool> {
+        self.get_unix_pex()?;
+        if uid == 0 {
+            return Some(true);
+        }
+        self.effective_pex(uid, gids).map(|pex| pex.can_read())
+    }
+
+    /// ### can_write
+    ///
+    /// Returns whether `uid` (belonging to `gids`) can write this `Entry`. See `can_read`
+    /// for the class-resolution semantics.
+    pub fn can_write(&self, uid: u32, gids: &[u32]) -> Option<bool> {
+        self.get_unix_pex()?;
+        if uid == 0 {
+            return Some(true);
+        }
+        self.effective_pex(uid, gids).map(|pex| pex.can_write())
+    }
+
+    /// ### can_execute
+    ///
+    /// Returns whether `uid` (belonging to `gids`) can execute this `Entry`. Unlike
+    /// `can_read`/`can_write`, root is only granted execute if at least one of the three
+    /// classes has the execute bit set, matching kernel semantics.
+    pub fn can_execute(&self, uid: u32, gids: &[u32]) -> Option<bool> {
+        let (owner, group, other) = self.get_unix_pex()?;
+        if uid == 0 {
+            return Some(owner.can_execute() || group.can_execute() || other.can_execute());
+        }
+        self.effective_pex(uid, gids).map(|pex| pex.can_execute())
+    }
+
+    /// ### effective_pex
+    ///
+    /// Resolve the `UnixPex` class that applies to `uid`/`gids`: owner if `uid` matches,
+    /// group if `gids` contains the entry's gid, otherwise other. Returns `None` when the
+    /// `Entry` has no unix permissions.
+    fn effective_pex(&self, uid: u32, gids: &[u32]) -> Option<UnixPex> {
+        let (owner, group, other) = self.get_unix_pex()?;
+        if Some(uid) == self.get_user() {
+            Some(owner)
+        } else if self.get_group().map(|gid| gids.contains(&gid)).unwrap_or(false) {
+            Some(group)
+        } else {
+            Some(other)
+        }
+    }
+
+    /// ### xattrs
+    ///
+    /// Get the extended attributes of `Entry`
+    pub fn xattrs(&self) -> &BTreeMap<String, Vec<u8>> {
+        match self {
+            Entry::Directory(dir) => &dir.xattrs,
+            Entry::File(file) => &file.xattrs,
+        }
+    }
+
+    /// ### get_xattr
+    ///
+    /// Get the value of the extended attribute `name`, if set
+    pub fn get_xattr(&self, name: &str) -> Option<&[u8]> {
+        self.xattrs().get(name).map(|value| value.as_slice())
+    }
+
     /// ### is_symlink
     ///
     /// Returns whether the `Entry` is a symlink
@@ -268,19 +501,51 @@ impl Entry {
         self.get_name().starts_with('.')
     }
 
+    /// The maximum number of symlink hops `get_realfile`/`resolve_target` will follow
+    /// before giving up, matching typical kernel `ELOOP` limits.
+    const MAX_SYMLINK_HOPS: usize = 40;
+
     /// ### get_realfile
     ///
-    /// Return the real file pointed by a `Entry`
+    /// Return the real file pointed by a `Entry`, following the symlink chain up to
+    /// `MAX_SYMLINK_HOPS` hops. If the chain is longer than that (e.g. a loop), the last
+    /// successfully resolved `Entry` is returned rather than recursing forever.
     pub fn get_realfile(&self) -> Entry {
-        match self {
-            Entry::Directory(dir) => match &dir.symlink {
-                Some(symlink) => symlink.get_realfile(),
-                None => self.clone(),
-            },
-            Entry::File(file) => match &file.symlink {
-                Some(symlink) => symlink.get_realfile(),
-                None => self.clone(),
-            },
+        let mut current: &Entry = self;
+        for _ in 0..Self::MAX_SYMLINK_HOPS {
+            let symlink = match current {
+                Entry::Directory(dir) => dir.symlink.as_deref(),
+                Entry::File(file) => file.symlink.as_deref(),
+            };
+            match symlink {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+        current.clone()
+    }
+
+    /// ### resolve_target
+    ///
+    /// Resolve the symlink chain without cloning, returning the last non-symlink `Entry`
+    /// by reference. Unlike `get_realfile`, this returns `Err(SymlinkError::TooManyHops)`
+    /// on a chain that looks like a loop, rather than silently returning a clone.
+    pub fn resolve_target(&self) -> Result<&Entry, SymlinkError> {
+        let mut current: &Entry = self;
+        for _ in 0..Self::MAX_SYMLINK_HOPS {
+            let symlink = match current {
+                Entry::Directory(dir) => dir.symlink.as_deref(),
+                Entry::File(file) => file.symlink.as_deref(),
+            };
+            match symlink {
+                Some(next) => current = next,
+                None => return Ok(current),
+            }
+        }
+        if current.is_symlink() {
+            Err(SymlinkError::TooManyHops)
+        } else {
+            Ok(current)
         }
     }
 
@@ -306,6 +571,273 @@ impl Entry {
     }
 }
 
+/// ## tar_header
+///
+/// Conversion layer between `Entry` and the `tar` crate's header format, so a directory
+/// tree of `Entry`s can be archived and restored as a single tar stream. Gated behind the
+/// `tar` feature so the core stays dependency-free.
+#[cfg(feature = "tar")]
+mod tar_header {
+    use super::{Directory, Entry, File, UnixMode, UnixPex};
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    impl File {
+        /// ### to_tar_header
+        ///
+        /// Build the `tar::Header` describing this `File`, plus, when `abs_path` doesn't fit
+        /// the 100-byte USTAR name field, the raw PAX extended-header record that must be
+        /// written as a preceding `EntryType::XHeader` entry for the full path to round-trip.
+        /// See `build_tar_header` for why a standalone `Header` can't carry that record itself.
+        pub fn to_tar_header(&self) -> (tar::Header, Option<Vec<u8>>) {
+            build_tar_header(
+                tar::EntryType::Regular,
+                &self.abs_path,
+                self.last_change_time,
+                self.user,
+                self.group,
+                tar_mode(self.unix_pex, self.unix_pex_special, 0o644),
+                self.size as u64,
+            )
+        }
+    }
+
+    impl Directory {
+        /// ### to_tar_header
+        ///
+        /// Build the `tar::Header` describing this `Directory`. See `File::to_tar_header` for
+        /// the PAX extension-record fallback semantics.
+        pub fn to_tar_header(&self) -> (tar::Header, Option<Vec<u8>>) {
+            build_tar_header(
+                tar::EntryType::Directory,
+                &self.abs_path,
+                self.last_change_time,
+                self.user,
+                self.group,
+                tar_mode(self.unix_pex, self.unix_pex_special, 0o755),
+                0,
+            )
+        }
+    }
+
+    /// Build a USTAR-based `tar::Header` plus, when needed, the PAX extended-header record
+    /// that must precede it in the stream.
+    ///
+    /// `uid`/`gid`/`mtime`/`size` overflow is handled transparently by the `tar` crate itself
+    /// (it falls back to its binary numeric-extension encoding), but a long `path` is not:
+    /// the PAX format represents it out-of-band, as a separate `x`-typed header+data entry
+    /// written immediately before the real one, which only a multi-entry writer (e.g.
+    /// `tar::Builder`) can emit. A bare `Header` has no field to put it in, so when
+    /// `set_path_absolute` fails we fall back to the file name alone for the USTAR field (keeping the
+    /// header itself valid) and return the PAX record for the caller to prepend.
+    fn build_tar_header(
+        entry_type: tar::EntryType,
+        abs_path: &Path,
+        mtime: SystemTime,
+        uid: Option<u32>,
+        gid: Option<u32>,
+        mode: u32,
+        size: u64,
+    ) -> (tar::Header, Option<Vec<u8>>) {
+        let mut header = tar::Header::new_ustar();
+        header.set_entry_type(entry_type);
+        header.set_size(size);
+        let mtime_secs = mtime.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        header.set_mtime(mtime_secs);
+        header.set_uid(uid.unwrap_or(0) as u64);
+        header.set_gid(gid.unwrap_or(0) as u64);
+        header.set_mode(mode);
+
+        let pax_extensions = match header.set_path_absolute(abs_path) {
+            Ok(()) => None,
+            Err(_) => {
+                let file_name = abs_path.file_name().unwrap_or_default();
+                // best effort so the header stays well-formed even without the PAX record
+                let _ = header.set_path_absolute(file_name);
+                Some(pax_record("path", abs_path.to_string_lossy().as_bytes()))
+            }
+        };
+        header.set_cksum();
+        (header, pax_extensions)
+    }
+
+    /// Encode a single PAX extended-header record: `"<len> <key>=<value>\n"`, where `<len>`
+    /// is the decimal length of the whole record, itself included.
+    fn pax_record(key: &str, value: &[u8]) -> Vec<u8> {
+        let mut len = key.len() + value.len() + 3; // b' ' + b'=' + b'\n'
+        loop {
+            let total = len.to_string().len() + key.len() + value.len() + 3;
+            if total == len {
+                break;
+            }
+            len = total;
+        }
+        let mut record = Vec::with_capacity(len);
+        record.extend_from_slice(len.to_string().as_bytes());
+        record.push(b' ');
+        record.extend_from_slice(key.as_bytes());
+        record.push(b'=');
+        record.extend_from_slice(value);
+        record.push(b'\n');
+        record
+    }
+
+    /// Compute the full octal mode (special bits included) for a tar header, falling back
+    /// to `default` when the entry carries no unix permissions.
+    fn tar_mode(
+        unix_pex: Option<(UnixPex, UnixPex, UnixPex)>,
+        unix_pex_special: Option<(bool, bool, bool)>,
+        default: u32,
+    ) -> u32 {
+        match unix_pex {
+            Some((owner, group, other)) => {
+                UnixMode::new(owner, group, other, unix_pex_special.unwrap_or_default()).as_mode() as u32
+            }
+            None => default,
+        }
+    }
+
+    impl Entry {
+        /// ### from_tar_header
+        ///
+        /// Reconstruct an `Entry` from a `tar::Header`: the directory/file variant is
+        /// picked from the tar entry type, `symlink` is filled from the link target when
+        /// the header describes a symlink, and the octal mode is split back into the
+        /// `(UnixPex, UnixPex, UnixPex)` triple plus special bits.
+        pub fn from_tar_header(header: &tar::Header, path: PathBuf) -> Entry {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let last_change_time = header
+                .mtime()
+                .ok()
+                .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+                .unwrap_or(UNIX_EPOCH);
+            let user = header.uid().ok().map(|uid| uid as u32);
+            let group = header.gid().ok().map(|gid| gid as u32);
+            let mode = UnixMode::from(header.mode().unwrap_or(0) as u16);
+            let unix_pex = Some((mode.owner(), mode.group(), mode.other()));
+            let unix_pex_special = Some((mode.setuid(), mode.setgid(), mode.sticky()));
+            let symlink = if header.entry_type().is_symlink() {
+                header.link_name().ok().flatten().map(|target| {
+                    Box::new(Entry::File(File {
+                        name: target
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_default(),
+                        abs_path: target.to_path_buf(),
+                        last_change_time,
+                        last_access_time: last_change_time,
+                        creation_time: last_change_time,
+                        size: 0,
+                        ftype: None,
+                        symlink: None,
+                        user,
+                        group,
+                        unix_pex,
+                        unix_pex_special,
+                        xattrs: BTreeMap::new(),
+                    }))
+                })
+            } else {
+                None
+            };
+            if header.entry_type().is_dir() {
+                Entry::Directory(Directory {
+                    name,
+                    abs_path: path,
+                    last_change_time,
+                    last_access_time: last_change_time,
+                    creation_time: last_change_time,
+                    symlink,
+                    user,
+                    group,
+                    unix_pex,
+                    unix_pex_special,
+                    xattrs: BTreeMap::new(),
+                })
+            } else {
+                Entry::File(File {
+                    name,
+                    abs_path: path,
+                    last_change_time,
+                    last_access_time: last_change_time,
+                    creation_time: last_change_time,
+                    size: header.size().unwrap_or(0) as usize,
+                    ftype: None,
+                    symlink,
+                    user,
+                    group,
+                    unix_pex,
+                    unix_pex_special,
+                    xattrs: BTreeMap::new(),
+                })
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn to_tar_header_short_path_needs_no_pax_record() {
+            let t_now: SystemTime = SystemTime::now();
+            let file = File {
+                name: String::from("bar.txt"),
+                abs_path: PathBuf::from("/bar.txt"),
+                last_change_time: t_now,
+                last_access_time: t_now,
+                creation_time: t_now,
+                size: 8,
+                ftype: None,
+                symlink: None,
+                user: Some(500),
+                group: Some(500),
+                unix_pex: Some((UnixPex::from(6), UnixPex::from(4), UnixPex::from(4))),
+                unix_pex_special: None,
+                xattrs: BTreeMap::new(),
+            };
+            let (header, pax_extensions) = file.to_tar_header();
+            assert_eq!(pax_extensions, None);
+            assert_eq!(header.path().unwrap().to_str(), Some("/bar.txt"));
+            assert_eq!(header.size().unwrap(), 8);
+            assert_eq!(header.mode().unwrap(), 0o644);
+        }
+
+        #[test]
+        fn to_tar_header_long_path_emits_pax_record() {
+            let t_now: SystemTime = SystemTime::now();
+            let long_path = PathBuf::from("/".to_string() + &"a".repeat(200));
+            let file = File {
+                name: "a".repeat(200),
+                abs_path: long_path.clone(),
+                last_change_time: t_now,
+                last_access_time: t_now,
+                creation_time: t_now,
+                size: 0,
+                ftype: None,
+                symlink: None,
+                user: None,
+                group: None,
+                unix_pex: None,
+                unix_pex_special: None,
+                xattrs: BTreeMap::new(),
+            };
+            let (header, pax_extensions) = file.to_tar_header();
+            // the USTAR field can't hold it, so the header alone carries a best-effort name...
+            assert_ne!(header.path().unwrap().to_str(), long_path.to_str());
+            // ...and the full path is carried in the PAX record the caller must prepend
+            let record = pax_extensions.expect("expected a pax extension record");
+            let record = String::from_utf8(record).unwrap();
+            assert!(record.ends_with(&format!("path={}\n", long_path.display())));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -325,6 +857,8 @@ mod tests {
             user: Some(0),  // UNIX only
             group: Some(0), // UNIX only
             unix_pex: Some((UnixPex::from(7), UnixPex::from(5), UnixPex::from(5))), // UNIX only
+            unix_pex_special: None, // UNIX only
+            xattrs: BTreeMap::new(),
         });
         assert_eq!(entry.get_abs_path(), PathBuf::from("/foo"));
         assert_eq!(entry.get_name(), String::from("foo"));
@@ -360,6 +894,8 @@ mod tests {
             user: Some(0),  // UNIX only
             group: Some(0), // UNIX only
             unix_pex: Some((UnixPex::from(6), UnixPex::from(4), UnixPex::from(4))), // UNIX only
+            unix_pex_special: None, // UNIX only
+            xattrs: BTreeMap::new(),
         });
         assert_eq!(entry.get_abs_path(), PathBuf::from("/bar.txt"));
         assert_eq!(entry.get_name(), String::from("bar.txt"));
@@ -396,6 +932,8 @@ mod tests {
             user: Some(0),  // UNIX only
             group: Some(0), // UNIX only
             unix_pex: Some((UnixPex::from(6), UnixPex::from(4), UnixPex::from(4))), // UNIX only
+            unix_pex_special: None, // UNIX only
+            xattrs: BTreeMap::new(),
         });
         entry.unwrap_dir();
     }
@@ -414,6 +952,8 @@ mod tests {
             user: Some(0),  // UNIX only
             group: Some(0), // UNIX only
             unix_pex: Some((UnixPex::from(7), UnixPex::from(5), UnixPex::from(5))), // UNIX only
+            unix_pex_special: None, // UNIX only
+            xattrs: BTreeMap::new(),
         });
         entry.unwrap_file();
     }
@@ -433,6 +973,8 @@ mod tests {
             user: Some(0),  // UNIX only
             group: Some(0), // UNIX only
             unix_pex: Some((UnixPex::from(6), UnixPex::from(4), UnixPex::from(4))), // UNIX only
+            unix_pex_special: None, // UNIX only
+            xattrs: BTreeMap::new(),
         });
         assert_eq!(entry.is_hidden(), false);
         let entry: Entry = Entry::File(File {
@@ -447,6 +989,8 @@ mod tests {
             user: Some(0),  // UNIX only
             group: Some(0), // UNIX only
             unix_pex: Some((UnixPex::from(6), UnixPex::from(4), UnixPex::from(4))), // UNIX only
+            unix_pex_special: None, // UNIX only
+            xattrs: BTreeMap::new(),
         });
         assert_eq!(entry.is_hidden(), true);
         let entry: Entry = Entry::Directory(Directory {
@@ -459,6 +1003,8 @@ mod tests {
             user: Some(0),  // UNIX only
             group: Some(0), // UNIX only
             unix_pex: Some((UnixPex::from(7), UnixPex::from(5), UnixPex::from(5))), // UNIX only
+            unix_pex_special: None, // UNIX only
+            xattrs: BTreeMap::new(),
         });
         assert_eq!(entry.is_hidden(), true);
     }
@@ -479,6 +1025,8 @@ mod tests {
             user: Some(0),  // UNIX only
             group: Some(0), // UNIX only
             unix_pex: Some((UnixPex::from(6), UnixPex::from(4), UnixPex::from(4))), // UNIX only
+            unix_pex_special: None, // UNIX only
+            xattrs: BTreeMap::new(),
         });
         // Symlink is None...
         assert_eq!(
@@ -496,6 +1044,8 @@ mod tests {
             user: Some(0),  // UNIX only
             group: Some(0), // UNIX only
             unix_pex: Some((UnixPex::from(7), UnixPex::from(5), UnixPex::from(5))), // UNIX only
+            unix_pex_special: None, // UNIX only
+            xattrs: BTreeMap::new(),
         });
         assert_eq!(entry.get_realfile().get_abs_path(), PathBuf::from("/foo"));
     }
@@ -515,6 +1065,8 @@ mod tests {
             user: Some(0),  // UNIX only
             group: Some(0), // UNIX only
             unix_pex: Some((UnixPex::from(7), UnixPex::from(7), UnixPex::from(7))), // UNIX only
+            unix_pex_special: None, // UNIX only
+            xattrs: BTreeMap::new(),
         });
         let entry_child: Entry = Entry::Directory(Directory {
             name: String::from("projects"),
@@ -526,6 +1078,8 @@ mod tests {
             user: Some(0),
             group: Some(0),
             unix_pex: Some((UnixPex::from(7), UnixPex::from(7), UnixPex::from(7))),
+            unix_pex_special: None, // UNIX only
+            xattrs: BTreeMap::new(),
         });
         let entry_root: Entry = Entry::File(File {
             name: String::from("projects"),
@@ -539,6 +1093,8 @@ mod tests {
             user: Some(0),
             group: Some(0),
             unix_pex: Some((UnixPex::from(7), UnixPex::from(7), UnixPex::from(7))),
+            unix_pex_special: None, // UNIX only
+            xattrs: BTreeMap::new(),
         });
         assert_eq!(entry_root.is_symlink(), true);
         // get real file
@@ -550,6 +1106,64 @@ mod tests {
         );
     }
 
+    /// Build a chain of `hops` nested symlinked files, the innermost one named `real.txt`
+    /// with no `symlink` set, and the outermost one returned.
+    fn build_symlink_chain(hops: usize) -> Entry {
+        let t_now: SystemTime = SystemTime::now();
+        let mut entry = Entry::File(File {
+            name: String::from("real.txt"),
+            abs_path: PathBuf::from("/real.txt"),
+            last_change_time: t_now,
+            last_access_time: t_now,
+            creation_time: t_now,
+            size: 1,
+            ftype: None,
+            symlink: None,
+            user: Some(0),
+            group: Some(0),
+            unix_pex: Some((UnixPex::from(7), UnixPex::from(7), UnixPex::from(7))),
+            unix_pex_special: None,
+            xattrs: BTreeMap::new(),
+        });
+        for i in 0..hops {
+            entry = Entry::File(File {
+                name: format!("link{}", i),
+                abs_path: PathBuf::from(format!("/link{}", i)),
+                last_change_time: t_now,
+                last_access_time: t_now,
+                creation_time: t_now,
+                size: 0,
+                ftype: None,
+                symlink: Some(Box::new(entry)),
+                user: Some(0),
+                group: Some(0),
+                unix_pex: Some((UnixPex::from(7), UnixPex::from(7), UnixPex::from(7))),
+                unix_pex_special: None,
+                xattrs: BTreeMap::new(),
+            });
+        }
+        entry
+    }
+
+    #[test]
+    fn fsentry_realfile_within_hop_limit() {
+        let entry = build_symlink_chain(Entry::MAX_SYMLINK_HOPS - 1);
+        assert_eq!(entry.get_realfile().get_abs_path(), PathBuf::from("/real.txt"));
+        assert_eq!(
+            entry.resolve_target().unwrap().get_abs_path(),
+            PathBuf::from("/real.txt")
+        );
+    }
+
+    #[test]
+    fn fsentry_realfile_beyond_hop_limit_does_not_panic() {
+        let entry = build_symlink_chain(Entry::MAX_SYMLINK_HOPS + 10);
+        // get_realfile must not overflow the stack; it just stops at the hop limit
+        let real_file = entry.get_realfile();
+        assert_ne!(real_file.get_abs_path(), PathBuf::from("/real.txt"));
+        assert_eq!(entry.resolve_target().unwrap_err(), SymlinkError::TooManyHops);
+    }
+
     #[test]
     fn unix_pex() {
         let pex: UnixPex = UnixPex::from(4);
@@ -573,4 +1187,161 @@ mod tests {
         let pex: UnixPex = UnixPex::from(7);
         assert_eq!(pex.as_byte(), 7);
     }
+
+    #[test]
+    fn unix_mode_from_u16_and_as_mode() {
+        // rwxr-xr-x, no special bits
+        let mode: UnixMode = UnixMode::from(0o755);
+        assert_eq!(mode.owner(), UnixPex::from(7));
+        assert_eq!(mode.group(), UnixPex::from(5));
+        assert_eq!(mode.other(), UnixPex::from(5));
+        assert_eq!(mode.setuid(), false);
+        assert_eq!(mode.setgid(), false);
+        assert_eq!(mode.sticky(), false);
+        assert_eq!(mode.as_mode(), 0o755);
+        // rwsr-sr-t, setuid + setgid + sticky
+        let mode: UnixMode = UnixMode::from(0o7755);
+        assert_eq!(mode.setuid(), true);
+        assert_eq!(mode.setgid(), true);
+        assert_eq!(mode.sticky(), true);
+        assert_eq!(mode.as_mode(), 0o7755);
+    }
+
+    #[test]
+    fn unix_mode_to_string() {
+        let mode: UnixMode = UnixMode::from(0o755);
+        assert_eq!(mode.to_string(), String::from("-rwxr-xr-x"));
+        // setuid with owner execute -> 's'; setgid without group execute -> 'S'
+        let mode: UnixMode = UnixMode::new(
+            UnixPex::from(7),
+            UnixPex::from(6),
+            UnixPex::from(5),
+            (true, true, true),
+        );
+        assert_eq!(mode.to_string(), String::from("-rwsrwSr-t"));
+    }
+
+    #[test]
+    fn fsentry_get_mode() {
+        let t_now: SystemTime = SystemTime::now();
+        let entry: Entry = Entry::File(File {
+            name: String::from("bar.txt"),
+            abs_path: PathBuf::from("/bar.txt"),
+            last_change_time: t_now,
+            last_access_time: t_now,
+            creation_time: t_now,
+            size: 8192,
+            ftype: Some(String::from("txt")),
+            symlink: None,
+            user: Some(0),
+            group: Some(0),
+            unix_pex: Some((UnixPex::from(6), UnixPex::from(4), UnixPex::from(4))),
+            unix_pex_special: Some((true, false, false)),
+            xattrs: BTreeMap::new(),
+        });
+        let mode: UnixMode = entry.get_mode().expect("expected a mode");
+        assert_eq!(mode.as_mode(), 0o4644);
+        // no unix_pex means no mode
+        let entry: Entry = Entry::Directory(Directory {
+            name: String::from("foo"),
+            abs_path: PathBuf::from("/foo"),
+            last_change_time: t_now,
+            last_access_time: t_now,
+            creation_time: t_now,
+            symlink: None,
+            user: None,
+            group: None,
+            unix_pex: None,
+            unix_pex_special: None,
+            xattrs: BTreeMap::new(),
+        });
+        assert_eq!(entry.get_mode(), None);
+    }
+
+    #[test]
+    fn fsentry_effective_permissions() {
+        let t_now: SystemTime = SystemTime::now();
+        let entry: Entry = Entry::File(File {
+            name: String::from("bar.txt"),
+            abs_path: PathBuf::from("/bar.txt"),
+            last_change_time: t_now,
+            last_access_time: t_now,
+            creation_time: t_now,
+            size: 8192,
+            ftype: Some(String::from("txt")),
+            symlink: None,
+            user: Some(500),
+            group: Some(500),
+            // rw-r-----
+            unix_pex: Some((UnixPex::from(6), UnixPex::from(4), UnixPex::from(0))),
+            unix_pex_special: None,
+            xattrs: BTreeMap::new(),
+        });
+        // root always wins, except execute which requires a set execute bit somewhere
+        assert_eq!(entry.can_read(0, &[]), Some(true));
+        assert_eq!(entry.can_write(0, &[]), Some(true));
+        assert_eq!(entry.can_execute(0, &[]), Some(false));
+        // owner
+        assert_eq!(entry.can_read(500, &[]), Some(true));
+        assert_eq!(entry.can_write(500, &[]), Some(true));
+        assert_eq!(entry.can_execute(500, &[]), Some(false));
+        // group
+        assert_eq!(entry.can_read(501, &[500]), Some(true));
+        assert_eq!(entry.can_write(501, &[500]), Some(false));
+        // other
+        assert_eq!(entry.can_read(501, &[501]), Some(false));
+        assert_eq!(entry.can_write(501, &[501]), Some(false));
+    }
+
+    #[test]
+    fn fsentry_effective_permissions_no_unix_pex() {
+        let t_now: SystemTime = SystemTime::now();
+        let entry: Entry = Entry::File(File {
+            name: String::from("bar.txt"),
+            abs_path: PathBuf::from("/bar.txt"),
+            last_change_time: t_now,
+            last_access_time: t_now,
+            creation_time: t_now,
+            size: 8192,
+            ftype: Some(String::from("txt")),
+            symlink: None,
+            user: None,
+            group: None,
+            unix_pex: None,
+            unix_pex_special: None,
+            xattrs: BTreeMap::new(),
+        });
+        assert_eq!(entry.can_read(500, &[]), None);
+        assert_eq!(entry.can_write(500, &[]), None);
+        assert_eq!(entry.can_execute(500, &[]), None);
+        // root must also get None rather than a free pass when there's no unix metadata
+        assert_eq!(entry.can_read(0, &[]), None);
+        assert_eq!(entry.can_write(0, &[]), None);
+        assert_eq!(entry.can_execute(0, &[]), None);
+    }
+
+    #[test]
+    fn fsentry_xattrs() {
+        let t_now: SystemTime = SystemTime::now();
+        let mut xattrs: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+        xattrs.insert(String::from("user.comment"), vec![0x61, 0x62, 0x63]);
+        let entry: Entry = Entry::File(File {
+            name: String::from("bar.txt"),
+            abs_path: PathBuf::from("/bar.txt"),
+            last_change_time: t_now,
+            last_access_time: t_now,
+            creation_time: t_now,
+            size: 8192,
+            ftype: Some(String::from("txt")),
+            symlink: None,
+            user: Some(0),
+            group: Some(0),
+            unix_pex: Some((UnixPex::from(6), UnixPex::from(4), UnixPex::from(4))),
+            unix_pex_special: None,
+            xattrs,
+        });
+        assert_eq!(entry.xattrs().len(), 1);
+        assert_eq!(entry.get_xattr("user.comment"), Some(b"abc".as_slice()));
+        assert_eq!(entry.get_xattr("user.missing"), None);
+    }
 }